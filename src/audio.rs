@@ -0,0 +1,56 @@
+use sdl2::audio::{AudioCallback, AudioDevice, AudioSpecDesired};
+use sdl2::AudioSubsystem;
+
+const FREQUENCY: i32 = 44_100;
+const TONE_HZ: f32 = 440.0;
+const VOLUME: f32 = 0.25;
+
+struct SquareWave {
+    phase: f32,
+    phase_step: f32,
+}
+
+impl AudioCallback for SquareWave {
+    type Channel = f32;
+
+    fn callback(&mut self, out: &mut [f32]) {
+        for sample in out.iter_mut() {
+            *sample = if self.phase < 0.5 { VOLUME } else { -VOLUME };
+            self.phase = (self.phase + self.phase_step) % 1.0;
+        }
+    }
+}
+
+// Wraps an SDL2 square wave audio device, gated on the CPU's sound timer
+// so games get a continuous tone for the timer's full duration instead of
+// a single beep on the final tick.
+pub struct Beeper {
+    device: AudioDevice<SquareWave>,
+}
+
+impl Beeper {
+    pub fn new(audio_subsystem: &AudioSubsystem) -> Self {
+        let spec = AudioSpecDesired {
+            freq: Some(FREQUENCY),
+            channels: Some(1),
+            samples: None,
+        };
+
+        let device = audio_subsystem
+            .open_playback(None, &spec, |spec| SquareWave {
+                phase: 0.0,
+                phase_step: TONE_HZ / spec.freq as f32,
+            })
+            .unwrap();
+
+        Self { device }
+    }
+
+    pub fn set_active(&self, active: bool) {
+        if active {
+            self.device.resume();
+        } else {
+            self.device.pause();
+        }
+    }
+}
@@ -1,5 +1,10 @@
 use crate::fontset::Fontset;
+use crate::quirks::Quirks;
 use crate::rand::Rand;
+use crate::snapshot::{SnapshotError, MAGIC, VERSION};
+use std::fs::File;
+use std::io;
+use std::io::Read;
 
 pub struct Chip8 {
     ram: [u8; 4096],     // 4k memory
@@ -12,10 +17,13 @@ pub struct Chip8 {
     delay_timer: u8,     // Delay Timer
     sound_timer: u8,     // Sound timer
     key: u16,            // Hex Keypad bit array
+    prev_key: u16,       // Hex Keypad bit array as of the previous frame, for FX0A edge detection
+    pub request_redraw: bool, // Set whenever vram changes so a frontend knows to repaint
+    quirks: Quirks,      // Opcode semantics that vary between CHIP-8 interpreters
 }
 
 impl Chip8 {
-    pub fn new() -> Self {
+    pub fn new(quirks: Quirks) -> Self {
         Self {
             ram: [0; 4096],
             vram: [0; 64 * 32],
@@ -27,6 +35,9 @@ impl Chip8 {
             delay_timer: 0,
             sound_timer: 0,
             key: 0,
+            prev_key: 0,
+            request_redraw: false,
+            quirks,
         }
     }
 
@@ -37,16 +48,25 @@ impl Chip8 {
         // Load fontset; fontset is stored in memory location 0x50
         let fontset = Fontset::new();
         for i in 0..80 {
-            self.ram[i] = fontset.data[i];
+            self.ram[0x50 + i] = fontset.data[i];
         }
     }
 
-    pub fn load_game(&mut self, game: &str) {
-        // Load game into ram starting from 0x200
-        let buffer_size = 512;
-        for i in 0..buffer_size {
-            self.ram[i + 0x200] = 0;
+    pub fn load_game(&mut self, game: &str) -> Result<(), io::Error> {
+        let mut file = File::open(game)?;
+        let mut buffer = Vec::new();
+        file.read_to_end(&mut buffer)?;
+
+        if buffer.len() > self.ram.len() - 0x200 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "ROM does not fit in memory",
+            ));
         }
+
+        self.ram[0x200..0x200 + buffer.len()].copy_from_slice(&buffer);
+
+        Ok(())
     }
 
     pub fn fetch_opcode(&mut self) -> u16 {
@@ -59,7 +79,11 @@ impl Chip8 {
             // Opcodes starting with 0x0 are base operations
             0x0000 => match opcode & 0x00FF {
                 // 00E0 => Clear screen
-                0x00E0 => { },
+                0x00E0 => {
+                    self.vram = [0; 64 * 32];
+                    self.request_redraw = true;
+                    self.next();
+                },
                 // 00EE => Return from subroutine
                 0x00EE => {
                     self.sp -= 1;
@@ -146,6 +170,9 @@ impl Chip8 {
                     let y = ((opcode & 0x00F0) >> 4) as usize;
 
                     self.v[x] |= self.v[y];
+                    if self.quirks.vf_reset_on_logic {
+                        self.v[0xF] = 0;
+                    }
                     self.next();
                 },
                 // 8XY2 => Set VX to VX & VY
@@ -154,6 +181,9 @@ impl Chip8 {
                     let y = ((opcode & 0x00F0) >> 4) as usize;
 
                     self.v[x] &= self.v[y];
+                    if self.quirks.vf_reset_on_logic {
+                        self.v[0xF] = 0;
+                    }
                     self.next();
                 },
                 // 8XY3 => Set VX to VX ^ VY
@@ -162,6 +192,9 @@ impl Chip8 {
                     let y = ((opcode & 0x00F0) >> 4) as usize;
 
                     self.v[x] ^= self.v[y];
+                    if self.quirks.vf_reset_on_logic {
+                        self.v[0xF] = 0;
+                    }
                     self.next();
                 },
                 // 8XY4 => Add VY to VX, set VF if there's a carry
@@ -186,12 +219,16 @@ impl Chip8 {
                     self.v[0xF] = if borrow { 1 } else { 0 };
                     self.next();
                 },
-                // 8XY6 => Shift VX right, store least significant bit of VX in VF
+                // 8XY6 => Shift VX (or VY, if shift_uses_vy) right, store the
+                //         least significant bit of the shifted value in VF
                 0x0006 => {
                     let x = ((opcode & 0x0F00) >> 8) as usize;
+                    let y = ((opcode & 0x00F0) >> 4) as usize;
 
-                    self.v[0xF] = self.v[x] & 0b1;
-                    self.v[x] >>= 1;
+                    let source = if self.quirks.shift_uses_vy { self.v[y] } else { self.v[x] };
+
+                    self.v[0xF] = source & 0b1;
+                    self.v[x] = source >> 1;
                     self.next();
                 },
                 // 8XY7 => Set VX to VY - VX, set VF to 0 if there's a borrow
@@ -205,12 +242,16 @@ impl Chip8 {
                     self.v[0xF] = if borrow { 1 } else { 0 };
                     self.next();
                 },
-                // 8XYE => Shift VX left, store most significant bit of VX in VF
+                // 8XYE => Shift VX (or VY, if shift_uses_vy) left, store the
+                //         most significant bit of the shifted value in VF
                 0x000E => {
                     let x = ((opcode & 0x0F00) >> 8) as usize;
+                    let y = ((opcode & 0x00F0) >> 4) as usize;
+
+                    let source = if self.quirks.shift_uses_vy { self.v[y] } else { self.v[x] };
 
-                    self.v[0xF] = (self.v[x] & 0b1000_0000) >> 7;
-                    self.v[x] <<= 1;
+                    self.v[0xF] = (source & 0b1000_0000) >> 7;
+                    self.v[x] = source << 1;
                     self.next();
                 },
                 // Default => print an error
@@ -236,10 +277,17 @@ impl Chip8 {
                 self.next();
             },
 
-            // BNNN => Jump to address NNN + V0
+            // BNNN => Jump to address NNN + V0 (or NNN + VX, if jump_with_vx)
             0xB000 => {
                 let addr = opcode & 0x0FFF;
-                self.pc = (addr + self.v[0x0] as u16) as usize;
+
+                let offset = if self.quirks.jump_with_vx {
+                    self.v[((addr & 0x0F00) >> 8) as usize]
+                } else {
+                    self.v[0x0]
+                };
+
+                self.pc = (addr + offset as u16) as usize;
             },
 
             // CXNN => Set VX to <random> & NN
@@ -253,12 +301,40 @@ impl Chip8 {
 
             // DXYN => Draw a sprite at (VX, VY) with a height of N+1
             //         Set VF if any pixels are flipped from set to unset
-            0xD000 => { },
+            0xD000 => {
+                let x = ((opcode & 0x0F00) >> 8) as usize;
+                let y = ((opcode & 0x00F0) >> 4) as usize;
+                let n = (opcode & 0x000F) as usize;
+
+                let vx = self.v[x] as usize % 64;
+                let vy = self.v[y] as usize % 32;
+
+                self.v[0xF] = 0;
+
+                for r in 0..n {
+                    let sprite_byte = self.ram[self.i as usize + r];
+
+                    for col in 0..8 {
+                        if sprite_byte & (0x80 >> col) != 0 {
+                            let px = ((vy + r) % 32) * 64 + ((vx + col) % 64);
+
+                            if self.vram[px] == 1 {
+                                self.v[0xF] = 1;
+                            }
+
+                            self.vram[px] ^= 1;
+                        }
+                    }
+                }
+
+                self.request_redraw = true;
+                self.next();
+            },
 
             // Opcodes starting with 0xE are keycode operations
             0xE000 => match opcode & 0x00FF {
                 // EX9E => Skip instruction if key stored in VX is pressed
-                0x00E9 => {
+                0x009E => {
                     let x = ((opcode & 0x0F00) >> 8) as usize;
 
                     if self.key & (1 << self.v[x]) != 0 {
@@ -291,7 +367,17 @@ impl Chip8 {
                     self.next();
                 },
                 // FX0A => Wait for input, then store key in VX
-                0x000A => {},
+                0x000A => {
+                    let x = ((opcode & 0x0F00) >> 8) as usize;
+                    let newly_pressed = self.key & !self.prev_key;
+
+                    if newly_pressed != 0 {
+                        self.v[x] = newly_pressed.trailing_zeros() as u8;
+                        self.next();
+                    }
+                    // else: leave pc untouched so the same opcode is re-fetched
+                    // next cycle until a key goes from unpressed to pressed
+                },
                 // FX15 => Set delay timer to VX
                 0x0015 => {
                     let x = ((opcode & 0x0F00) >> 8) as usize;
@@ -313,7 +399,13 @@ impl Chip8 {
                     self.i = self.i.wrapping_add(self.v[x] as u16);
                 },
                 // FX29 => Set i to location of sprite for value in VX
-                0x0029 => {},
+                0x0029 => {
+                    let x = ((opcode & 0x0F00) >> 8) as usize;
+
+                    // Fontset lives at 0x50, 5 bytes per hex glyph
+                    self.i = 0x50 + (self.v[x] & 0x0F) as u16 * 5;
+                    self.next();
+                },
                 // FX33 => Store 3-digit binary-coded decimal of VX in memory at address i..i+2
                 0x0033 => {
                     let x = ((opcode & 0x0F00) >> 8) as usize;
@@ -327,18 +419,30 @@ impl Chip8 {
                 },
                 // FX55 => Dump registers 0..X to ram, starting at address i
                 0x0055 => {
-                    for j in 0..=((opcode & 0x0F00) >> 8) as usize {
+                    let x = ((opcode & 0x0F00) >> 8) as usize;
+
+                    for j in 0..=x {
                         self.ram[self.i as usize + j] = self.v[j];
                     }
 
+                    if self.quirks.load_store_increments_i {
+                        self.i += x as u16 + 1;
+                    }
+
                     self.next();
                 },
                 // FX65 => Fill registers 0..X with data from ram, starting at address i
                 0x0065 => {
-                    for j in 0..=((opcode & 0x0F00) >> 8) as usize {
+                    let x = ((opcode & 0x0F00) >> 8) as usize;
+
+                    for j in 0..=x {
                         self.v[j] = self.ram[self.i as usize + j];
                     }
 
+                    if self.quirks.load_store_increments_i {
+                        self.i += x as u16 + 1;
+                    }
+
                     self.next();
                 },
                 // Default => print an error
@@ -352,25 +456,160 @@ impl Chip8 {
                 eprintln!("Unknown opcode: {:x}", opcode);
             }
         }
+    }
 
-        // Update timers
+    // Decrements the delay and sound timers. Must be called at a fixed 60Hz,
+    // independent of how many opcodes `execute` runs per frame, or every ROM
+    // runs at the wrong speed.
+    pub fn tick_timers(&mut self) {
         if self.delay_timer > 0 {
             self.delay_timer -= 1;
         }
 
         if self.sound_timer > 0 {
-            if self.sound_timer == 1 {
-                println!("BEEP!");
-            }
             self.sound_timer -= 1;
         }
     }
 
-    pub fn next(&self) {
+    pub fn is_beeping(&self) -> bool {
+        self.sound_timer > 0
+    }
+
+    pub fn next(&mut self) {
         self.pc += 2;
     }
 
-    pub fn set_keys(&self) {
-        unimplemented!();
+    pub fn set_keys(&mut self, keys: u16) {
+        self.prev_key = self.key;
+        self.key = keys;
+    }
+
+    pub fn vram(&self) -> &[u8; 64 * 32] {
+        &self.vram
+    }
+
+    pub fn pc(&self) -> usize {
+        self.pc
+    }
+
+    pub fn i(&self) -> u16 {
+        self.i
+    }
+
+    pub fn sp(&self) -> usize {
+        self.sp
+    }
+
+    pub fn v(&self) -> &[u8; 16] {
+        &self.v
+    }
+
+    pub fn stack(&self) -> &[u16; 16] {
+        &self.stack
+    }
+
+    // Serializes the full machine state to a versioned byte layout.
+    pub fn snapshot(&self) -> Vec<u8> {
+        let mut data = Vec::with_capacity(4 + 1 + 4096 + 2048 + 32 + 1 + 16 + 2 + 2 + 1 + 1 + 2);
+
+        data.extend_from_slice(MAGIC);
+        data.push(VERSION);
+        data.extend_from_slice(&self.ram);
+        data.extend_from_slice(&self.vram);
+
+        for slot in &self.stack {
+            data.extend_from_slice(&slot.to_le_bytes());
+        }
+
+        data.push(self.sp as u8);
+        data.extend_from_slice(&self.v);
+        data.extend_from_slice(&self.i.to_le_bytes());
+        data.extend_from_slice(&(self.pc as u16).to_le_bytes());
+        data.push(self.delay_timer);
+        data.push(self.sound_timer);
+        data.extend_from_slice(&self.key.to_le_bytes());
+
+        data
+    }
+
+    // Restores machine state previously produced by `snapshot`, rejecting
+    // data with a missing/mismatched magic header, an unsupported version,
+    // or a truncated length.
+    pub fn restore(&mut self, data: &[u8]) -> Result<(), SnapshotError> {
+        let mut cursor = 0;
+
+        let take = |cursor: &mut usize, len: usize| -> Result<std::ops::Range<usize>, SnapshotError> {
+            let range = *cursor..*cursor + len;
+            if range.end > data.len() {
+                return Err(SnapshotError::TooShort);
+            }
+            *cursor += len;
+            Ok(range)
+        };
+
+        if data.len() < MAGIC.len() + 1 || &data[..MAGIC.len()] != MAGIC {
+            return Err(SnapshotError::BadMagic);
+        }
+        cursor += MAGIC.len();
+
+        let version = data[cursor];
+        cursor += 1;
+        if version != VERSION {
+            return Err(SnapshotError::UnsupportedVersion(version));
+        }
+
+        // Check the total length before touching any state, so rejected
+        // data leaves the machine unchanged.
+        let expected_len = MAGIC.len() + 1 + self.ram.len() + self.vram.len()
+            + self.stack.len() * 2 + 1 + self.v.len() + 2 + 2 + 1 + 1 + 2;
+        if data.len() < expected_len {
+            return Err(SnapshotError::TooShort);
+        }
+        if data.len() > expected_len {
+            return Err(SnapshotError::TooLong);
+        }
+
+        let ram_range = take(&mut cursor, self.ram.len())?;
+        self.ram.copy_from_slice(&data[ram_range]);
+
+        let vram_range = take(&mut cursor, self.vram.len())?;
+        self.vram.copy_from_slice(&data[vram_range]);
+
+        for slot in self.stack.iter_mut() {
+            let range = take(&mut cursor, 2)?;
+            *slot = u16::from_le_bytes([data[range.start], data[range.start + 1]]);
+        }
+
+        let sp_range = take(&mut cursor, 1)?;
+        self.sp = data[sp_range.start] as usize;
+
+        let v_range = take(&mut cursor, self.v.len())?;
+        self.v.copy_from_slice(&data[v_range]);
+
+        let i_range = take(&mut cursor, 2)?;
+        self.i = u16::from_le_bytes([data[i_range.start], data[i_range.start + 1]]);
+
+        let pc_range = take(&mut cursor, 2)?;
+        self.pc = u16::from_le_bytes([data[pc_range.start], data[pc_range.start + 1]]) as usize;
+
+        let delay_range = take(&mut cursor, 1)?;
+        self.delay_timer = data[delay_range.start];
+
+        let sound_range = take(&mut cursor, 1)?;
+        self.sound_timer = data[sound_range.start];
+
+        let key_range = take(&mut cursor, 2)?;
+        self.key = u16::from_le_bytes([data[key_range.start], data[key_range.start + 1]]);
+        self.prev_key = self.key;
+
+        if cursor != data.len() {
+            return Err(SnapshotError::TooLong);
+        }
+
+        // The restored vram differs from whatever the frontend last drew,
+        // so force a repaint instead of waiting for the ROM's next draw.
+        self.request_redraw = true;
+
+        Ok(())
     }
 }
@@ -0,0 +1,81 @@
+use crate::cpu::Chip8;
+use crate::disassemble::disassemble;
+use std::io::{self, Write};
+
+// Drives a single-step debugger session: prints machine state before every
+// cycle and blocks for a command, so a misbehaving ROM can be diagnosed
+// instead of just emitting "Unknown opcode" and carrying on.
+pub struct Debugger {
+    stepping: bool,
+    breakpoints: Vec<usize>,
+}
+
+impl Debugger {
+    pub fn new() -> Self {
+        Self {
+            stepping: true,
+            breakpoints: Vec::new(),
+        }
+    }
+
+    // Called with each fetched opcode before it is executed. Returns false once the
+    // user asks to quit.
+    pub fn before_cycle(&mut self, chip8: &Chip8, opcode: u16) -> bool {
+        if !self.stepping && !self.breakpoints.contains(&chip8.pc()) {
+            return true;
+        }
+        self.stepping = true;
+
+        loop {
+            println!(
+                "pc=0x{:03X} opcode=0x{:04X}  {}",
+                chip8.pc(),
+                opcode,
+                disassemble(opcode)
+            );
+            print_regs(chip8);
+            print!("(debugger) ");
+            io::stdout().flush().unwrap();
+
+            let mut input = String::new();
+            if io::stdin().read_line(&mut input).unwrap_or(0) == 0 {
+                return false;
+            }
+
+            let input = input.trim();
+            let mut parts = input.split_whitespace();
+
+            match parts.next() {
+                None => return true, // bare enter: single-step one cycle
+                Some("continue") | Some("c") => {
+                    self.stepping = false;
+                    return true;
+                }
+                Some("break") => match parts.next().and_then(parse_addr) {
+                    Some(addr) => {
+                        println!("Breakpoint set at 0x{:03X}", addr);
+                        self.breakpoints.push(addr);
+                    }
+                    None => println!("Usage: break <addr>"),
+                },
+                Some("regs") => print_regs(chip8),
+                Some("quit") | Some("q") => return false,
+                Some(other) => println!("Unknown command: {}", other),
+            }
+        }
+    }
+}
+
+fn print_regs(chip8: &Chip8) {
+    println!(
+        "  v={:02X?} i=0x{:04X} sp={} stack={:04X?}",
+        chip8.v(),
+        chip8.i(),
+        chip8.sp(),
+        chip8.stack()
+    );
+}
+
+fn parse_addr(s: &str) -> Option<usize> {
+    usize::from_str_radix(s.trim_start_matches("0x"), 16).ok()
+}
@@ -0,0 +1,59 @@
+// Renders an opcode as a readable mnemonic, using the same nibble-decoding
+// layout as `Chip8::execute`, so this stays easy to keep in sync with it.
+pub fn disassemble(opcode: u16) -> String {
+    let x = ((opcode & 0x0F00) >> 8) as usize;
+    let y = ((opcode & 0x00F0) >> 4) as usize;
+    let n = opcode & 0x000F;
+    let nn = opcode & 0x00FF;
+    let nnn = opcode & 0x0FFF;
+
+    match opcode & 0xF000 {
+        0x0000 => match opcode & 0x00FF {
+            0x00E0 => "CLS".to_string(),
+            0x00EE => "RET".to_string(),
+            _ => format!("UNKNOWN {:04X}", opcode),
+        },
+        0x1000 => format!("JP 0x{:03X}", nnn),
+        0x2000 => format!("CALL 0x{:03X}", nnn),
+        0x3000 => format!("SE V{:X}, 0x{:02X}", x, nn),
+        0x4000 => format!("SNE V{:X}, 0x{:02X}", x, nn),
+        0x5000 => format!("SE V{:X}, V{:X}", x, y),
+        0x6000 => format!("LD V{:X}, 0x{:02X}", x, nn),
+        0x7000 => format!("ADD V{:X}, 0x{:02X}", x, nn),
+        0x8000 => match opcode & 0x000F {
+            0x0000 => format!("LD V{:X}, V{:X}", x, y),
+            0x0001 => format!("OR V{:X}, V{:X}", x, y),
+            0x0002 => format!("AND V{:X}, V{:X}", x, y),
+            0x0003 => format!("XOR V{:X}, V{:X}", x, y),
+            0x0004 => format!("ADD V{:X}, V{:X}", x, y),
+            0x0005 => format!("SUB V{:X}, V{:X}", x, y),
+            0x0006 => format!("SHR V{:X}, V{:X}", x, y),
+            0x0007 => format!("SUBN V{:X}, V{:X}", x, y),
+            0x000E => format!("SHL V{:X}, V{:X}", x, y),
+            _ => format!("UNKNOWN {:04X}", opcode),
+        },
+        0x9000 => format!("SNE V{:X}, V{:X}", x, y),
+        0xA000 => format!("LD I, 0x{:03X}", nnn),
+        0xB000 => format!("JP V0, 0x{:03X}", nnn),
+        0xC000 => format!("RND V{:X}, 0x{:02X}", x, nn),
+        0xD000 => format!("DRW V{:X}, V{:X}, {}", x, y, n),
+        0xE000 => match opcode & 0x00FF {
+            0x009E => format!("SKP V{:X}", x),
+            0x00A1 => format!("SKNP V{:X}", x),
+            _ => format!("UNKNOWN {:04X}", opcode),
+        },
+        0xF000 => match opcode & 0x00FF {
+            0x0007 => format!("LD V{:X}, DT", x),
+            0x000A => format!("LD V{:X}, K", x),
+            0x0015 => format!("LD DT, V{:X}", x),
+            0x0018 => format!("LD ST, V{:X}", x),
+            0x001E => format!("ADD I, V{:X}", x),
+            0x0029 => format!("LD F, V{:X}", x),
+            0x0033 => format!("LD B, V{:X}", x),
+            0x0055 => format!("LD [I], V{:X}", x),
+            0x0065 => format!("LD V{:X}, [I]", x),
+            _ => format!("UNKNOWN {:04X}", opcode),
+        },
+        _ => format!("UNKNOWN {:04X}", opcode),
+    }
+}
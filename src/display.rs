@@ -0,0 +1,115 @@
+use sdl2::event::Event;
+use sdl2::keyboard::Keycode;
+use sdl2::pixels::Color;
+use sdl2::rect::Rect;
+use sdl2::render::Canvas;
+use sdl2::video::Window;
+use sdl2::EventPump;
+use sdl2::Sdl;
+
+const SCALE: u32 = 10;
+const WIDTH: u32 = 64;
+const HEIGHT: u32 = 32;
+
+// What happened on screen/input this frame.
+pub struct FrameInput {
+    pub keys: u16,
+    pub should_quit: bool,
+    pub save_state: bool,
+    pub load_state: bool,
+}
+
+pub struct Display {
+    canvas: Canvas<Window>,
+    event_pump: EventPump,
+}
+
+impl Display {
+    pub fn new(sdl_context: &Sdl) -> Self {
+        let video = sdl_context.video().unwrap();
+        let window = video
+            .window("chip8_rs", WIDTH * SCALE, HEIGHT * SCALE)
+            .position_centered()
+            .build()
+            .unwrap();
+
+        let canvas = window.into_canvas().build().unwrap();
+        let event_pump = sdl_context.event_pump().unwrap();
+
+        Self { canvas, event_pump }
+    }
+
+    // Redraws the whole screen from vram; callers should only do this when
+    // Chip8::request_redraw is set.
+    pub fn draw(&mut self, vram: &[u8; (WIDTH * HEIGHT) as usize]) {
+        self.canvas.set_draw_color(Color::RGB(0, 0, 0));
+        self.canvas.clear();
+        self.canvas.set_draw_color(Color::RGB(255, 255, 255));
+
+        for y in 0..HEIGHT {
+            for x in 0..WIDTH {
+                if vram[(y * WIDTH + x) as usize] != 0 {
+                    let rect = Rect::new((x * SCALE) as i32, (y * SCALE) as i32, SCALE, SCALE);
+                    self.canvas.fill_rect(rect).unwrap();
+                }
+            }
+        }
+
+        self.canvas.present();
+    }
+
+    // Polls pending SDL events and the current keyboard state: the CHIP-8
+    // hex keypad bitmask, whether the frontend should quit, and whether the
+    // save-state hotkeys (F5 save, F9 load) were pressed this frame.
+    pub fn poll_keys(&mut self) -> FrameInput {
+        let mut should_quit = false;
+        let mut save_state = false;
+        let mut load_state = false;
+
+        for event in self.event_pump.poll_iter() {
+            match event {
+                Event::Quit { .. } => should_quit = true,
+                Event::KeyDown { keycode: Some(Keycode::F5), .. } => save_state = true,
+                Event::KeyDown { keycode: Some(Keycode::F9), .. } => load_state = true,
+                _ => {}
+            }
+        }
+
+        let keys = self
+            .event_pump
+            .keyboard_state()
+            .pressed_scancodes()
+            .filter_map(Keycode::from_scancode)
+            .filter_map(map_key)
+            .fold(0u16, |acc, hex_key| acc | (1 << hex_key));
+
+        FrameInput { keys, should_quit, save_state, load_state }
+    }
+}
+
+// Standard QWERTY CHIP-8 keypad layout:
+//   1 2 3 4        1 2 3 C
+//   Q W E R   ->   4 5 6 D
+//   A S D F        7 8 9 E
+//   Z X C V        A 0 B F
+fn map_key(keycode: Keycode) -> Option<u8> {
+    match keycode {
+        Keycode::Num1 => Some(0x1),
+        Keycode::Num2 => Some(0x2),
+        Keycode::Num3 => Some(0x3),
+        Keycode::Num4 => Some(0xC),
+        Keycode::Q => Some(0x4),
+        Keycode::W => Some(0x5),
+        Keycode::E => Some(0x6),
+        Keycode::R => Some(0xD),
+        Keycode::A => Some(0x7),
+        Keycode::S => Some(0x8),
+        Keycode::D => Some(0x9),
+        Keycode::F => Some(0xE),
+        Keycode::Z => Some(0xA),
+        Keycode::X => Some(0x0),
+        Keycode::C => Some(0xB),
+        Keycode::V => Some(0xF),
+        _ => None,
+    }
+}
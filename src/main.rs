@@ -1,10 +1,154 @@
+mod audio;
 mod rand;
 mod fontset;
 mod cpu;
+mod debugger;
+mod disassemble;
+mod display;
+mod quirks;
+mod snapshot;
 
-use rand::Rand;
+use audio::Beeper;
 use cpu::Chip8;
+use debugger::Debugger;
+use display::Display;
+use quirks::Quirks;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process;
+use std::thread;
+use std::time::{Duration, Instant, SystemTime};
+
+// Opcodes per second; the timers always run at a fixed 60Hz regardless of this.
+const CPU_HZ: u32 = 600;
+const TIMER_HZ: u32 = 60;
 
 fn main() {
-    println!("{}", Rand::random_u8().unwrap());
+    let args: Vec<String> = env::args().collect();
+
+    let rom_path = args.get(1).cloned().unwrap_or_else(|| {
+        eprintln!("Usage: chip8_rs <rom> [--shift-uses-vy] [--load-store-increments-i] [--jump-with-vx] [--vf-reset-on-logic] [--step]");
+        process::exit(1);
+    });
+
+    let quirks = Quirks {
+        shift_uses_vy: args.iter().any(|a| a == "--shift-uses-vy"),
+        load_store_increments_i: args.iter().any(|a| a == "--load-store-increments-i"),
+        jump_with_vx: args.iter().any(|a| a == "--jump-with-vx"),
+        vf_reset_on_logic: args.iter().any(|a| a == "--vf-reset-on-logic"),
+    };
+
+    let mut chip8 = Chip8::new(quirks);
+    chip8.initialize();
+
+    if let Err(e) = chip8.load_game(&rom_path) {
+        eprintln!("Failed to load ROM {}: {}", rom_path, e);
+        process::exit(1);
+    }
+
+    if args.iter().any(|a| a == "--step") {
+        run_debug_loop(&mut chip8);
+        return;
+    }
+
+    let sdl_context = sdl2::init().unwrap();
+    let mut display = Display::new(&sdl_context);
+    let beeper = Beeper::new(&sdl_context.audio().unwrap());
+
+    let opcodes_per_timer_tick = CPU_HZ / TIMER_HZ;
+    let frame_duration = Duration::from_secs(1) / TIMER_HZ;
+
+    loop {
+        let frame_start = Instant::now();
+
+        let input = display.poll_keys();
+        if input.should_quit {
+            break;
+        }
+        chip8.set_keys(input.keys);
+
+        if input.save_state {
+            let path = save_state_path(&rom_path);
+            if let Err(e) = fs::write(&path, chip8.snapshot()) {
+                eprintln!("Failed to write save state {}: {}", path.display(), e);
+            }
+        }
+
+        if input.load_state {
+            match find_latest_state_file(&rom_path) {
+                Some(path) => match fs::read(&path) {
+                    Ok(data) => {
+                        if let Err(e) = chip8.restore(&data) {
+                            eprintln!("Failed to load save state {}: {}", path.display(), e);
+                        }
+                    }
+                    Err(e) => eprintln!("Failed to read save state {}: {}", path.display(), e),
+                },
+                None => eprintln!("No save state found for {}", rom_path),
+            }
+        }
+
+        for _ in 0..opcodes_per_timer_tick {
+            let opcode = chip8.fetch_opcode();
+            chip8.execute(opcode);
+        }
+        chip8.tick_timers();
+        beeper.set_active(chip8.is_beeping());
+
+        if chip8.request_redraw {
+            display.draw(chip8.vram());
+            chip8.request_redraw = false;
+        }
+
+        let elapsed = frame_start.elapsed();
+        if elapsed < frame_duration {
+            thread::sleep(frame_duration - elapsed);
+        }
+    }
+}
+
+// Single-step debugger loop: runs one opcode at a time, gated by `Debugger`,
+// instead of the normal 600Hz/60Hz frame loop.
+fn run_debug_loop(chip8: &mut Chip8) {
+    let mut debugger = Debugger::new();
+
+    loop {
+        let opcode = chip8.fetch_opcode();
+
+        if !debugger.before_cycle(chip8, opcode) {
+            break;
+        }
+
+        chip8.execute(opcode);
+        chip8.tick_timers();
+    }
+}
+
+fn save_state_path(rom_path: &str) -> PathBuf {
+    let mut path = PathBuf::from(rom_path);
+    let file_name = format!("{}.state", path.file_name().unwrap_or_default().to_string_lossy());
+    path.set_file_name(file_name);
+    path
+}
+
+// Picks the most recently modified `*.state` file next to the ROM, rather
+// than assuming the exact `<rom>.state` name, so loading still works if the
+// state was saved under a different ROM file name.
+fn find_latest_state_file(rom_path: &str) -> Option<PathBuf> {
+    let dir = match Path::new(rom_path).parent() {
+        Some(p) if !p.as_os_str().is_empty() => p,
+        _ => Path::new("."),
+    };
+
+    fs::read_dir(dir)
+        .ok()?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "state"))
+        .max_by_key(|path| {
+            fs::metadata(path)
+                .and_then(|m| m.modified())
+                .unwrap_or(SystemTime::UNIX_EPOCH)
+        })
 }
@@ -0,0 +1,14 @@
+// Different CHIP-8 interpreters disagree on a handful of opcodes. These
+// flags let the frontend pick the behavior a given ROM expects instead of
+// hardcoding one interpreter's choices.
+#[derive(Default)]
+pub struct Quirks {
+    // 8XY6/8XYE shift VY into VX instead of shifting VX in place
+    pub shift_uses_vy: bool,
+    // FX55/FX65 leave `i` incremented by X+1 after the loop (original COSMAC VIP behavior)
+    pub load_store_increments_i: bool,
+    // BNNN jumps to NNN + V[(NNN>>8)&0xF] instead of NNN + V0
+    pub jump_with_vx: bool,
+    // 8XY1/8XY2/8XY3 reset VF to 0
+    pub vf_reset_on_logic: bool,
+}
@@ -0,0 +1,31 @@
+use std::error;
+use std::fmt;
+
+// MAGIC marks a chip8_rs snapshot. VERSION is bumped whenever the layout
+// written by Chip8::snapshot changes, so old save states are rejected
+// instead of silently misread.
+pub const MAGIC: &[u8; 4] = b"C8RS";
+pub const VERSION: u8 = 1;
+
+#[derive(Debug)]
+pub enum SnapshotError {
+    TooShort,
+    TooLong,
+    BadMagic,
+    UnsupportedVersion(u8),
+}
+
+impl fmt::Display for SnapshotError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SnapshotError::TooShort => write!(f, "snapshot data is too short"),
+            SnapshotError::TooLong => write!(f, "snapshot data is longer than expected"),
+            SnapshotError::BadMagic => write!(f, "not a chip8_rs snapshot file"),
+            SnapshotError::UnsupportedVersion(v) => {
+                write!(f, "unsupported snapshot version: {}", v)
+            }
+        }
+    }
+}
+
+impl error::Error for SnapshotError {}